@@ -0,0 +1,151 @@
+use crate::io;
+use crate::litterman;
+use std::error::Error;
+
+/// One historical rebalance point: the Black-Litterman inputs trained on the
+/// trailing window, plus what actually happened afterwards. `realized_returns`
+/// and `benchmark_return` are marked over the holding period that follows this
+/// snapshot, so results stay genuinely out-of-sample.
+pub struct Snapshot {
+    pub sigma: Vec<Vec<f64>>,
+    pub market_weights: Vec<f64>,
+    pub p: Vec<Vec<f64>>,
+    pub q: Vec<f64>,
+    pub omega: Vec<Vec<f64>>,
+    pub realized_returns: Vec<f64>, // per-asset return over the following holding period
+    pub benchmark_return: f64,      // market-cap-weighted benchmark return over the same period
+}
+
+/// Load a sequence of snapshots from `<dir>/period_0`, `<dir>/period_1`, ...
+/// (stopping at the first missing subdirectory). Each period directory holds
+/// the same Matrix Market files `run_from_matrixmarket` reads for a single
+/// run — `sigma.mtx`, `market_weights.mtx`, `p.mtx`, `q.mtx`, `omega.mtx` —
+/// plus `realized_returns.mtx` (per-asset return over the holding period)
+/// and a one-value `benchmark_return.mtx`.
+pub fn load_snapshots(dir: &str) -> Result<Vec<Snapshot>, Box<dyn Error>> {
+    let mut snapshots = Vec::new();
+    for i in 0.. {
+        let period_dir = format!("{}/period_{}", dir, i);
+        if !std::path::Path::new(&period_dir).is_dir() {
+            break;
+        }
+
+        let benchmark_return = io::read_matrix_market_vector(&format!("{}/benchmark_return.mtx", period_dir))?
+            .into_iter()
+            .next()
+            .ok_or("benchmark_return.mtx must contain exactly one value")?;
+
+        snapshots.push(Snapshot {
+            sigma: io::read_matrix_market(&format!("{}/sigma.mtx", period_dir))?,
+            market_weights: io::read_matrix_market_vector(&format!("{}/market_weights.mtx", period_dir))?,
+            p: io::read_matrix_market(&format!("{}/p.mtx", period_dir))?,
+            q: io::read_matrix_market_vector(&format!("{}/q.mtx", period_dir))?,
+            omega: io::read_matrix_market(&format!("{}/omega.mtx", period_dir))?,
+            realized_returns: io::read_matrix_market_vector(&format!("{}/realized_returns.mtx", period_dir))?,
+            benchmark_return,
+        });
+    }
+    Ok(snapshots)
+}
+
+/// Out-of-sample performance of the walk-forward strategy vs. the benchmark.
+#[derive(Debug, Default)]
+pub struct BacktestReport {
+    pub cumulative_return: f64,
+    pub annualized_sharpe: f64,
+    pub max_drawdown: f64,
+    pub turnover: f64,
+    pub benchmark_cumulative_return: f64,
+}
+
+/// Walk-forward (rolling-window) evaluation: for each snapshot, run
+/// black_litterman + mvo on the trailing-window inputs, hold the resulting
+/// weights over the next period, and accumulate realized portfolio returns.
+/// `periods_per_year` annualizes the Sharpe ratio (e.g. 12 for monthly
+/// rebalances, 252 for daily).
+pub fn walk_forward(
+    snapshots: &[Snapshot],
+    tau: f64,
+    delta: f64,
+    periods_per_year: f64,
+) -> Result<BacktestReport, litterman::LinAlgError> {
+    let mut strategy_returns = Vec::with_capacity(snapshots.len());
+    let mut benchmark_returns = Vec::with_capacity(snapshots.len());
+    let mut prev_weights: Option<Vec<f64>> = None;
+    let mut turnover_sum = 0.0;
+
+    for snapshot in snapshots {
+        let posterior_mean = litterman::black_litterman(
+            &snapshot.sigma,
+            &snapshot.market_weights,
+            tau,
+            delta,
+            &snapshot.p,
+            &snapshot.q,
+            &snapshot.omega,
+        )?;
+        let weights = litterman::mvo(&snapshot.sigma, posterior_mean)?;
+
+        if let Some(prev) = &prev_weights {
+            turnover_sum += weights
+                .iter()
+                .zip(prev.iter())
+                .map(|(w, p)| (w - p).abs())
+                .sum::<f64>();
+        }
+
+        let period_return: f64 = weights
+            .iter()
+            .zip(snapshot.realized_returns.iter())
+            .map(|(w, r)| w * r)
+            .sum();
+
+        strategy_returns.push(period_return);
+        benchmark_returns.push(snapshot.benchmark_return);
+        prev_weights = Some(weights);
+    }
+
+    Ok(BacktestReport {
+        cumulative_return: cumulative_return(&strategy_returns),
+        annualized_sharpe: annualized_sharpe(&strategy_returns, periods_per_year),
+        max_drawdown: max_drawdown(&strategy_returns),
+        turnover: if snapshots.len() > 1 {
+            turnover_sum / (snapshots.len() - 1) as f64
+        } else {
+            0.0
+        },
+        benchmark_cumulative_return: cumulative_return(&benchmark_returns),
+    })
+}
+
+// Compound per-period returns into a single cumulative return.
+fn cumulative_return(returns: &[f64]) -> f64 {
+    returns.iter().fold(1.0, |acc, r| acc * (1.0 + r)) - 1.0
+}
+
+// Sharpe ratio of the per-period returns, annualized by sqrt(periods_per_year).
+fn annualized_sharpe(returns: &[f64], periods_per_year: f64) -> f64 {
+    if returns.len() < 2 {
+        return 0.0;
+    }
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+    let std_dev = variance.sqrt();
+    if std_dev < 1e-12 {
+        return 0.0;
+    }
+    (mean / std_dev) * periods_per_year.sqrt()
+}
+
+// Largest peak-to-trough decline of the compounded equity curve.
+fn max_drawdown(returns: &[f64]) -> f64 {
+    let mut equity = 1.0;
+    let mut peak = 1.0;
+    let mut worst = 0.0;
+    for &r in returns {
+        equity *= 1.0 + r;
+        peak = peak.max(equity);
+        worst = worst.min((equity - peak) / peak);
+    }
+    worst
+}
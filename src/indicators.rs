@@ -0,0 +1,146 @@
+/// Simple moving average over a trailing `window`. Time-aligned with `closes`:
+/// index `i` holds the average of `closes[i + 1 - window ..= i]`, `NaN` before
+/// the window has filled.
+pub fn sma(closes: &[f64], window: usize) -> Vec<f64> {
+    let mut out = vec![f64::NAN; closes.len()];
+    if window == 0 || closes.len() < window {
+        return out;
+    }
+
+    let mut sum: f64 = closes[..window].iter().sum();
+    out[window - 1] = sum / window as f64;
+    for i in window..closes.len() {
+        sum += closes[i] - closes[i - window];
+        out[i] = sum / window as f64;
+    }
+    out
+}
+
+/// Exponential moving average, seeded with the SMA of the first `window`
+/// points. Time-aligned with `closes`.
+pub fn ema(closes: &[f64], window: usize) -> Vec<f64> {
+    let mut out = vec![f64::NAN; closes.len()];
+    if window == 0 || closes.len() < window {
+        return out;
+    }
+
+    let alpha = 2.0 / (window as f64 + 1.0);
+    out[window - 1] = closes[..window].iter().sum::<f64>() / window as f64;
+    for i in window..closes.len() {
+        out[i] = alpha * closes[i] + (1.0 - alpha) * out[i - 1];
+    }
+    out
+}
+
+/// Relative Strength Index over `period` closes using Wilder's smoothing.
+/// `period` has no default value; the caller always passes it.
+/// Time-aligned with `closes`.
+pub fn rsi(closes: &[f64], period: usize) -> Vec<f64> {
+    let mut out = vec![f64::NAN; closes.len()];
+    if period == 0 || closes.len() <= period {
+        return out;
+    }
+
+    let mut avg_gain = 0.0;
+    let mut avg_loss = 0.0;
+    for i in 1..=period {
+        let change = closes[i] - closes[i - 1];
+        if change >= 0.0 {
+            avg_gain += change;
+        } else {
+            avg_loss -= change;
+        }
+    }
+    avg_gain /= period as f64;
+    avg_loss /= period as f64;
+    out[period] = rsi_from_averages(avg_gain, avg_loss);
+
+    for i in (period + 1)..closes.len() {
+        let change = closes[i] - closes[i - 1];
+        let gain = change.max(0.0);
+        let loss = (-change).max(0.0);
+        avg_gain = (avg_gain * (period as f64 - 1.0) + gain) / period as f64;
+        avg_loss = (avg_loss * (period as f64 - 1.0) + loss) / period as f64;
+        out[i] = rsi_from_averages(avg_gain, avg_loss);
+    }
+    out
+}
+
+fn rsi_from_averages(avg_gain: f64, avg_loss: f64) -> f64 {
+    if avg_loss.abs() < 1e-12 {
+        return 100.0;
+    }
+    let rs = avg_gain / avg_loss;
+    100.0 - (100.0 / (1.0 + rs))
+}
+
+/// MACD = EMA(fast) − EMA(slow), with a `signal`-period EMA of the MACD line
+/// and the histogram (MACD − signal). All three vectors are time-aligned
+/// with the input close series.
+pub struct Macd {
+    pub macd_line: Vec<f64>,
+    pub signal_line: Vec<f64>,
+    pub histogram: Vec<f64>,
+}
+
+pub fn macd(closes: &[f64], fast: usize, slow: usize, signal: usize) -> Macd {
+    let ema_fast = ema(closes, fast);
+    let ema_slow = ema(closes, slow);
+
+    let mut macd_line = vec![f64::NAN; closes.len()];
+    if slow == 0 || closes.len() < slow {
+        return Macd { macd_line: macd_line.clone(), signal_line: macd_line.clone(), histogram: macd_line };
+    }
+    let start = slow - 1;
+    for i in start..closes.len() {
+        macd_line[i] = ema_fast[i] - ema_slow[i];
+    }
+
+    // `ema` assumes a contiguous, non-NaN input, so smooth only the valid
+    // (post-warmup) tail of the MACD line and splice the result back in.
+    let signal_valid = ema(&macd_line[start..], signal);
+    let mut signal_line = vec![f64::NAN; closes.len()];
+    for (i, v) in signal_valid.into_iter().enumerate() {
+        signal_line[start + i] = v;
+    }
+
+    let histogram: Vec<f64> = macd_line
+        .iter()
+        .zip(signal_line.iter())
+        .map(|(m, s)| m - s)
+        .collect();
+
+    Macd { macd_line, signal_line, histogram }
+}
+
+/// Bollinger Bands: SMA ± k·(rolling population std dev). Time-aligned with
+/// the input close series.
+pub struct BollingerBands {
+    pub upper: Vec<f64>,
+    pub middle: Vec<f64>,
+    pub lower: Vec<f64>,
+}
+
+pub fn bollinger_bands(closes: &[f64], window: usize, k: f64) -> BollingerBands {
+    let middle = sma(closes, window);
+    let mut upper = vec![f64::NAN; closes.len()];
+    let mut lower = vec![f64::NAN; closes.len()];
+
+    if window == 0 || closes.len() < window {
+        return BollingerBands { upper, middle, lower };
+    }
+
+    for i in (window - 1)..closes.len() {
+        let mean = middle[i];
+        let variance = closes[i + 1 - window..=i]
+            .iter()
+            .map(|c| (c - mean).powi(2))
+            .sum::<f64>()
+            / window as f64;
+        let std_dev = variance.sqrt();
+        upper[i] = mean + k * std_dev;
+        lower[i] = mean - k * std_dev;
+    }
+
+    BollingerBands { upper, middle, lower }
+}
@@ -2,10 +2,13 @@
 use chrono::Utc;
 use chrono::Duration;
 use std::error::Error;
+use std::time::{Duration as StdDuration, Instant};
 use time::OffsetDateTime;
 use yahoo_finance_api as yahoo;
 use scraper::{Html, Selector};
 use regex::Regex;
+use dashmap::DashMap;
+use futures::future::join_all;
 
 // Get the CIK (Central Index Key) for a given stock ticker
 pub fn get_cik(ticker: &str) -> Result<String, Box<dyn Error>> {
@@ -37,6 +40,95 @@ pub fn get_cik(ticker: &str) -> Result<String, Box<dyn Error>> {
     Err(format!("CIK not found for ticker: {}", ticker).into())
 }
 
+// Levenshtein edit distance between two strings (case-insensitive).
+fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let (n, m) = (a.len(), b.len());
+
+    let mut dp = vec![vec![0usize; m + 1]; n + 1];
+    for (i, row) in dp.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for j in 0..=m {
+        dp[0][j] = j;
+    }
+
+    for i in 1..=n {
+        for j in 1..=m {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            dp[i][j] = (dp[i - 1][j] + 1)
+                .min(dp[i][j - 1] + 1)
+                .min(dp[i - 1][j - 1] + cost);
+        }
+    }
+
+    dp[n][m]
+}
+
+// Normalized similarity in [0, 1]: 1.0 for an exact (case-insensitive) match,
+// 0.0 for completely dissimilar strings.
+fn similarity(a: &str, b: &str) -> f64 {
+    let max_len = a.chars().count().max(b.chars().count());
+    if max_len == 0 {
+        return 1.0;
+    }
+    1.0 - (levenshtein(a, b) as f64 / max_len as f64)
+}
+
+/// A fuzzy-match candidate returned by `resolve_company`.
+#[derive(Debug, Clone)]
+pub struct CompanyMatch {
+    pub cik: String,
+    pub ticker: String,
+    pub name: String,
+    pub score: f64, // similarity in [0, 1], higher is a better match
+}
+
+/// Resolve a ticker or approximate company name to its top candidate CIK
+/// matches. Tries an exact uppercased-ticker match first (same as `get_cik`);
+/// only on a miss does it fall back to fuzzy-scoring `query` against both the
+/// `ticker` and `title` fields, so a typo or a name like "Tesla" still finds
+/// TSLA. Candidates below `threshold` are dropped; the rest are ranked
+/// highest-score first.
+pub fn resolve_company(query: &str, threshold: f64) -> Result<Vec<CompanyMatch>, Box<dyn Error>> {
+    if let Ok(cik) = get_cik(query) {
+        return Ok(vec![CompanyMatch {
+            cik,
+            ticker: query.to_uppercase(),
+            name: query.to_string(),
+            score: 1.0,
+        }]);
+    }
+
+    let json_data = include_str!("company_tickers.json");
+    let json: serde_json::Value = serde_json::from_str(json_data)?;
+
+    let mut matches = Vec::new();
+    if let Some(obj) = json.as_object() {
+        for (_, company) in obj {
+            let ticker = company.get("ticker").and_then(|v| v.as_str()).unwrap_or("");
+            let name = company.get("title").and_then(|v| v.as_str()).unwrap_or("");
+            let score = similarity(query, ticker).max(similarity(query, name));
+
+            if score < threshold {
+                continue;
+            }
+            if let Some(cik_num) = company.get("cik_str").and_then(|v| v.as_u64()) {
+                matches.push(CompanyMatch {
+                    cik: format!("{:010}", cik_num),
+                    ticker: ticker.to_string(),
+                    name: name.to_string(),
+                    score,
+                });
+            }
+        }
+    }
+
+    matches.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(matches)
+}
+
 // Fetch SEC filings for a given CIK
 pub async fn fetch_sec_filings(cik: &str) -> Result<serde_json::Value, Box<dyn Error>> {
     let url = format!("https://data.sec.gov/api/xbrl/companyfacts/CIK{}.json", cik);
@@ -84,6 +176,49 @@ async fn get_latest_quote(ticker: &str) -> Result<yahoo::YResponse, Box<dyn Erro
     provider.get_latest_quotes(ticker, "1d").await.map_err(|e| e.into())
 }
 
+// Get a split-and-dividend-adjusted close series for a given ticker, using
+// Yahoo's `adjclose` field rather than raw `close`. Raw close is distorted by
+// stock splits and dividends and corrupts covariance/volatility estimates.
+async fn get_adjusted_closes(ticker: &str, days: i64) -> Result<Vec<f64>, Box<dyn Error>> {
+    let response = get_stock_history(ticker, days).await?;
+    let quotes = response
+        .quotes()
+        .map_err(|_| "Failed to get quotes from Yahoo response")?;
+    Ok(quotes.iter().map(|quote| quote.adjclose).collect())
+}
+
+/// A split ratio or dividend cash amount Yahoo reports alongside a ticker's
+/// quote history, so downstream analysis can reason about total return vs.
+/// price return instead of just the adjusted close series.
+#[derive(Debug, Clone)]
+pub struct CorporateActions {
+    pub splits: Vec<(i64, f64)>,    // (unix timestamp, split ratio, e.g. 2.0 for a 2-for-1)
+    pub dividends: Vec<(i64, f64)>, // (unix timestamp, dividend amount per share)
+}
+
+// Extract the corporate actions Yahoo returns alongside a ticker's quote
+// history.
+pub async fn get_corporate_actions(ticker: &str, days: i64) -> Result<CorporateActions, Box<dyn Error>> {
+    let response = get_stock_history(ticker, days).await?;
+
+    let splits = response
+        .splits()
+        .map(|events| {
+            events
+                .iter()
+                .map(|s| (s.date, s.numerator / s.denominator))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let dividends = response
+        .dividends()
+        .map(|events| events.iter().map(|d| (d.date, d.amount)).collect())
+        .unwrap_or_default();
+
+    Ok(CorporateActions { splits, dividends })
+}
+
 // Parse JSON from SEC filings to extract financial data
 pub fn parse_json(json: &serde_json::Value) -> Vec<Option<f64>> {
     let mut revenue_data = Vec::with_capacity(2);
@@ -305,46 +440,275 @@ pub async fn get_market_weights(tickers: Vec<&str>) -> Result<Vec<f64>, Box<dyn
     Ok(market_weights)
 }
 
-// Get covariance matrix for a list of tickers
+// Get covariance matrix for a list of tickers. Uses split-and-dividend
+// adjusted close so corporate actions don't corrupt the estimate.
 pub async fn get_covariance_matrix(tickers: Vec<&str>) -> Result<Vec<Vec<f64>>, Box<dyn Error>> {
-    let n = tickers.len();
-    let mut prices = Vec::with_capacity(n);
-    
-    // Fetch historical prices for each ticker
+    let mut prices = Vec::with_capacity(tickers.len());
+
+    // Fetch historical adjusted prices for each ticker
     for ticker in tickers {
-        let response = get_stock_history(ticker, 365).await?;
-        if let Ok(quotes) = response.quotes() {
-            prices.push(quotes.iter().map(|quote| quote.close).collect::<Vec<f64>>());
-        } else {
-            return Err("Failed to get quotes for covariance calculation".into());
-        }
+        prices.push(get_adjusted_closes(ticker, 365).await?);
     }
 
-    // Calculate means
+    Ok(covariance_from_series(&prices))
+}
+
+// Sample covariance matrix from one adjusted-close (or return) series per
+// asset. Shared by `get_covariance_matrix` and `DataProvider::get_covariance_matrix`
+// so the concurrent-fetch path and the sequential one compute it identically.
+fn covariance_from_series(prices: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let n = prices.len();
+
     let mut means = Vec::with_capacity(n);
-    for price_series in &prices {
+    for price_series in prices {
         let sum: f64 = price_series.iter().sum();
         means.push(sum / price_series.len() as f64);
     }
-    
-    // Calculate covariance matrix
+
     let mut covariance_matrix = vec![vec![0.0; n]; n];
     for i in 0..n {
         for j in i..n {  // Use symmetry to reduce calculations
             let price_count = prices[i].len().min(prices[j].len());
             let mut cov = 0.0;
-            
+
             for k in 0..price_count {
                 cov += (prices[i][k] - means[i]) * (prices[j][k] - means[j]);
             }
-            
+
             let val = cov / (price_count as f64 - 1.0);
             covariance_matrix[i][j] = val;
             covariance_matrix[j][i] = val;  // Symmetric matrix
         }
     }
 
-    Ok(covariance_matrix)
+    covariance_matrix
+}
+
+// Convert a price series into simple period-over-period returns
+fn to_returns(prices: &[f64]) -> Vec<f64> {
+    prices
+        .windows(2)
+        .map(|w| (w[1] - w[0]) / w[0])
+        .collect()
+}
+
+// Ledoit-Wolf shrinkage estimate of the covariance matrix: the sample
+// covariance S shrunk towards the scaled-identity target F = (trace(S)/N)·I,
+// with the shrinkage intensity chosen to minimize expected estimation error.
+// This is always well-conditioned and invertible, unlike the raw sample
+// covariance `get_covariance_matrix` falls back to for a handful of tickers.
+pub fn shrink_covariance(returns: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let n = returns.len();
+    if n == 0 || returns.iter().any(|series| series.is_empty()) {
+        return Vec::new();
+    }
+    // Tickers can come back with different history lengths (late listings,
+    // fetch gaps), so every series (means included) is truncated to the
+    // length of the shortest series in `returns`.
+    let t = returns.iter().map(|series| series.len()).min().unwrap();
+
+    let means: Vec<f64> = returns
+        .iter()
+        .map(|series| series[..t].iter().sum::<f64>() / t as f64)
+        .collect();
+
+    // Sample covariance S
+    let mut s = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in i..n {
+            let mut cov = 0.0;
+            for k in 0..t {
+                cov += (returns[i][k] - means[i]) * (returns[j][k] - means[j]);
+            }
+            let val = cov / (t as f64 - 1.0);
+            s[i][j] = val;
+            s[j][i] = val;
+        }
+    }
+
+    // Shrinkage target: scaled identity F = (trace(S)/N)·I
+    let trace: f64 = (0..n).map(|i| s[i][i]).sum();
+    let mu = trace / n as f64;
+
+    // d^2 = ||S - F||_F^2
+    let mut d2 = 0.0;
+    for i in 0..n {
+        for j in 0..n {
+            let f_ij = if i == j { mu } else { 0.0 };
+            d2 += (s[i][j] - f_ij).powi(2);
+        }
+    }
+
+    // b^2 = (1/T^2) * sum_t ||r_t r_t' - S||_F^2, capped at d^2 so the
+    // intensity never exceeds 1
+    let mut b2 = 0.0;
+    for k in 0..t {
+        for i in 0..n {
+            let ri = returns[i][k] - means[i];
+            for j in 0..n {
+                let rj = returns[j][k] - means[j];
+                b2 += (ri * rj - s[i][j]).powi(2);
+            }
+        }
+    }
+    b2 /= (t as f64).powi(2);
+    b2 = b2.min(d2);
+
+    let delta = if d2 > 1e-12 { (b2 / d2).clamp(0.0, 1.0) } else { 0.0 };
+
+    let mut shrunk = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in 0..n {
+            let f_ij = if i == j { mu } else { 0.0 };
+            shrunk[i][j] = (1.0 - delta) * s[i][j] + delta * f_ij;
+        }
+    }
+
+    shrunk
+}
+
+// Get a Ledoit-Wolf shrunk covariance matrix for a list of tickers. Same data
+// source as `get_covariance_matrix`, but estimated on returns rather than raw
+// prices and shrunk towards a well-conditioned target before being handed to
+// `black_litterman`/`mvo`.
+pub async fn get_covariance_matrix_shrunk(tickers: Vec<&str>) -> Result<Vec<Vec<f64>>, Box<dyn Error>> {
+    let n = tickers.len();
+    let mut returns = Vec::with_capacity(n);
+
+    for ticker in tickers {
+        let prices = get_adjusted_closes(ticker, 365).await?;
+        returns.push(to_returns(&prices));
+    }
+
+    Ok(shrink_covariance(&returns))
+}
+
+// Parse a Matrix Market file (coordinate or dense array format) into a dense
+// matrix. Lets a run skip the live SEC/news/Yahoo fetch entirely and load a
+// precomputed Σ, market-weight vector, or view matrix instead, so results are
+// reproducible and diffable across runs.
+pub fn read_matrix_market(path: &str) -> Result<Vec<Vec<f64>>, Box<dyn Error>> {
+    let contents = std::fs::read_to_string(path)?;
+    let mut lines = contents.lines();
+
+    let banner = lines.next().ok_or("empty Matrix Market file")?;
+    if !banner.starts_with("%%MatrixMarket") {
+        return Err("missing %%MatrixMarket banner".into());
+    }
+    let is_array = banner.contains("array");
+
+    let header = lines
+        .by_ref()
+        .find(|line| !line.starts_with('%'))
+        .ok_or("missing dimension line")?;
+    let dims: Vec<usize> = header
+        .split_whitespace()
+        .map(|d| d.parse::<usize>())
+        .collect::<Result<_, _>>()?;
+
+    if is_array {
+        let rows = *dims.first().ok_or("dimension line must have at least 1 value")?;
+        let cols = *dims.get(1).unwrap_or(&1);
+        if rows == 0 || cols == 0 {
+            return Err("array dimensions must be non-zero".into());
+        }
+
+        let values: Vec<f64> = lines
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .map(|line| line.parse::<f64>())
+            .collect::<Result<_, _>>()?;
+
+        if values.len() != rows * cols {
+            return Err(format!(
+                "expected {} values for a {}x{} array, found {}",
+                rows * cols,
+                rows,
+                cols,
+                values.len()
+            )
+            .into());
+        }
+
+        // Array format is stored column-major
+        let mut matrix = vec![vec![0.0; cols]; rows];
+        for (idx, val) in values.into_iter().enumerate() {
+            matrix[idx % rows][idx / rows] = val;
+        }
+        Ok(matrix)
+    } else {
+        if dims.len() < 2 {
+            return Err("coordinate dimension line must have at least rows and cols".into());
+        }
+        let (rows, cols) = (dims[0], dims[1]);
+        if rows == 0 || cols == 0 {
+            return Err("coordinate dimensions must be non-zero".into());
+        }
+        let mut matrix = vec![vec![0.0; cols]; rows];
+
+        for line in lines.map(|l| l.trim()).filter(|l| !l.is_empty()) {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 3 {
+                return Err(format!("malformed coordinate entry: {:?}", line).into());
+            }
+            let r: usize = parts[0].parse()?;
+            let c: usize = parts[1].parse()?;
+            let v: f64 = parts[2].parse()?;
+            if r == 0 || r > rows || c == 0 || c > cols {
+                return Err(format!(
+                    "coordinate entry ({}, {}) out of bounds for a {}x{} matrix",
+                    r, c, rows, cols
+                )
+                .into());
+            }
+            // Matrix Market coordinate indices are 1-based
+            matrix[r - 1][c - 1] = v;
+        }
+        Ok(matrix)
+    }
+}
+
+// Write a dense matrix in Matrix Market coordinate format (explicit zeros are
+// skipped), so the matrices a run produces can be diffed or reloaded later.
+pub fn write_matrix_market(path: &str, matrix: &[Vec<f64>]) -> Result<(), Box<dyn Error>> {
+    let rows = matrix.len();
+    let cols = matrix.first().map(|r| r.len()).unwrap_or(0);
+
+    let entries: Vec<(usize, usize, f64)> = matrix
+        .iter()
+        .enumerate()
+        .flat_map(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, &v)| v != 0.0)
+                .map(move |(j, &v)| (i + 1, j + 1, v))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    let mut out = String::from("%%MatrixMarket matrix coordinate real general\n");
+    out.push_str(&format!("{} {} {}\n", rows, cols, entries.len()));
+    for (r, c, v) in entries {
+        out.push_str(&format!("{} {} {}\n", r, c, v));
+    }
+
+    std::fs::write(path, out).map_err(|e| e.into())
+}
+
+// Read a dense vector (market weights, Q) stored as a Matrix Market array.
+pub fn read_matrix_market_vector(path: &str) -> Result<Vec<f64>, Box<dyn Error>> {
+    let matrix = read_matrix_market(path)?;
+    Ok(matrix.into_iter().map(|row| row[0]).collect())
+}
+
+// Write a dense vector as a single-column Matrix Market array.
+pub fn write_matrix_market_vector(path: &str, vector: &[f64]) -> Result<(), Box<dyn Error>> {
+    let mut out = String::from("%%MatrixMarket matrix array real general\n");
+    out.push_str(&format!("{} 1\n", vector.len()));
+    for &v in vector {
+        out.push_str(&format!("{}\n", v));
+    }
+    std::fs::write(path, out).map_err(|e| e.into())
 }
 
 // Create an uncertainty matrix for a list of tickers
@@ -358,4 +722,111 @@ pub fn get_uncertainty_matrix(tickers: Vec<&str>) -> Vec<Vec<f64>> {
     }
 
     uncertainty_matrix
+}
+
+// Cached value plus when it was fetched, so callers can decide whether it's
+// still fresh enough to reuse.
+#[derive(Clone)]
+struct CacheEntry<T> {
+    value: T,
+    fetched_at: Instant,
+}
+
+/// A reusable data-access layer: owns a single `YahooConnector`/`reqwest::Client`
+/// instead of the free functions above constructing a new one per call, and
+/// caches responses in memory keyed by (ticker, range) with a TTL. Use this
+/// for multi-ticker runs where the free functions would otherwise re-fetch
+/// the same history window repeatedly.
+pub struct DataProvider {
+    yahoo: yahoo::YahooConnector,
+    http: reqwest::Client,
+    history_cache: DashMap<(String, i64), CacheEntry<Vec<f64>>>,
+    sec_cache: DashMap<String, CacheEntry<serde_json::Value>>,
+    ttl: StdDuration,
+}
+
+impl DataProvider {
+    pub fn new(ttl: StdDuration) -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            yahoo: yahoo::YahooConnector::new()?,
+            http: reqwest::Client::new(),
+            history_cache: DashMap::new(),
+            sec_cache: DashMap::new(),
+            ttl,
+        })
+    }
+
+    // Fetch a split-and-dividend adjusted close series for one ticker,
+    // serving from the in-memory cache when the entry is still within `ttl`.
+    pub async fn get_adjusted_closes(&self, ticker: &str, days: i64) -> Result<Vec<f64>, Box<dyn Error>> {
+        let key = (ticker.to_string(), days);
+        if let Some(entry) = self.history_cache.get(&key) {
+            if entry.fetched_at.elapsed() < self.ttl {
+                return Ok(entry.value.clone());
+            }
+        }
+
+        let end = Utc::now();
+        let start = end - Duration::days(days);
+        let start_odt = OffsetDateTime::from_unix_timestamp(start.timestamp())?;
+        let end_odt = OffsetDateTime::from_unix_timestamp(end.timestamp())?;
+
+        let response = self.yahoo.get_quote_history(ticker, start_odt, end_odt).await?;
+        let quotes = response
+            .quotes()
+            .map_err(|_| "Failed to get quotes from Yahoo response")?;
+        let closes: Vec<f64> = quotes.iter().map(|q| q.adjclose).collect();
+
+        self.history_cache.insert(
+            key,
+            CacheEntry { value: closes.clone(), fetched_at: Instant::now() },
+        );
+        Ok(closes)
+    }
+
+    // Fetch adjusted closes for many tickers concurrently instead of one
+    // history request per ticker in sequence.
+    pub async fn get_adjusted_closes_many(
+        &self,
+        tickers: &[&str],
+        days: i64,
+    ) -> Result<Vec<Vec<f64>>, Box<dyn Error>> {
+        join_all(tickers.iter().map(|ticker| self.get_adjusted_closes(ticker, days)))
+            .await
+            .into_iter()
+            .collect()
+    }
+
+    // Covariance matrix over many tickers, built from the concurrently
+    // fetched (and cached) adjusted-close series.
+    pub async fn get_covariance_matrix(&self, tickers: &[&str]) -> Result<Vec<Vec<f64>>, Box<dyn Error>> {
+        let prices = self.get_adjusted_closes_many(tickers, 365).await?;
+        Ok(covariance_from_series(&prices))
+    }
+
+    // Fetch SEC companyfacts for a CIK, serving from cache when fresh.
+    pub async fn fetch_sec_filings(&self, cik: &str) -> Result<serde_json::Value, Box<dyn Error>> {
+        if let Some(entry) = self.sec_cache.get(cik) {
+            if entry.fetched_at.elapsed() < self.ttl {
+                return Ok(entry.value.clone());
+            }
+        }
+
+        let url = format!("https://data.sec.gov/api/xbrl/companyfacts/CIK{}.json", cik);
+        let body = self
+            .http
+            .get(url)
+            .header("User-Agent", "optimizeme/1.0 (jleechris06@gmail.com)")
+            .send()
+            .await?
+            .text()
+            .await?;
+        let json: serde_json::Value = serde_json::from_str(&body)?;
+
+        self.sec_cache.insert(
+            cik.to_string(),
+            CacheEntry { value: json.clone(), fetched_at: Instant::now() },
+        );
+        Ok(json)
+    }
 }
\ No newline at end of file
@@ -1,356 +1,349 @@
-// Matrix multiplication
-fn mat_mult(a: &[Vec<f64>], b: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
-    // Handle empty matrices
-    if a.is_empty() || b.is_empty() || a[0].is_empty() || b[0].is_empty() {
-        eprintln!("Empty matrix in multiplication");
-        return None;
-    }
-    
-    let rows = a.len();
-    let cols = b[0].len();
-    let a_cols = a[0].len();
-    let b_rows = b.len();
-    
-    // Validate dimensions
-    if a_cols != b_rows {
-        eprintln!("Matrix dimensions don't match for multiplication: {} != {}", a_cols, b_rows);
-        return None;
-    }
-    
-    // Create result matrix with pre-allocated capacity
-    let mut result = vec![vec![0.0; cols]; rows];
-    
-    // Perform multiplication (cache-friendly ordering)
-    for i in 0..rows {
-        for k in 0..a_cols {
-            let a_ik = a[i][k];
-            for j in 0..cols {
-                result[i][j] += a_ik * b[k][j];
-            }
-        }
-    }
-    
-    Some(result)
+use nalgebra::{DMatrix, DVector};
+use std::error::Error;
+use std::fmt;
+
+/// Errors surfaced by the linear-algebra layer backing Black-Litterman and MVO.
+#[derive(Debug)]
+pub enum LinAlgError {
+    DimensionMismatch(String),
+    SingularMatrix(String),
 }
 
-// Transpose a matrix
-fn transpose(mat: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
-    // Handle empty matrix
-    if mat.is_empty() || mat[0].is_empty() {
-        eprintln!("Empty matrix in transpose");
-        return None;
-    }
-    
-    let rows = mat.len();
-    let cols = mat[0].len();
-    
-    // Validate consistent row lengths
-    if mat.iter().any(|row| row.len() != cols) {
-        eprintln!("Inconsistent row lengths in transpose");
-        return None;
-    }
-    
-    // Create transposed matrix
-    let mut transposed = vec![vec![0.0; rows]; cols];
-    
-    // Perform transposition
-    for i in 0..rows {
-        for j in 0..cols {
-            transposed[j][i] = mat[i][j];
+impl fmt::Display for LinAlgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinAlgError::DimensionMismatch(msg) => write!(f, "dimension mismatch: {}", msg),
+            LinAlgError::SingularMatrix(msg) => write!(f, "singular matrix: {}", msg),
         }
     }
-    
-    Some(transposed)
 }
 
-// Identity matrix generator
-fn identity_matrix(size: usize) -> Vec<Vec<f64>> {
-    let mut identity = vec![vec![0.0; size]; size];
-    for i in 0..size {
-        identity[i][i] = 1.0;
+impl Error for LinAlgError {}
+
+// Convert a row-major Vec<Vec<f64>> into a DMatrix, checking every row has the
+// same width first so `from_row_slice` never silently misreads the shape.
+fn to_dmatrix(rows: &[Vec<f64>]) -> Result<DMatrix<f64>, LinAlgError> {
+    let n_rows = rows.len();
+    let n_cols = rows.first().map(|r| r.len()).unwrap_or(0);
+    if n_rows == 0 || n_cols == 0 || rows.iter().any(|row| row.len() != n_cols) {
+        return Err(LinAlgError::DimensionMismatch(
+            "ragged or empty matrix".into(),
+        ));
     }
-    identity
+    Ok(DMatrix::from_row_slice(n_rows, n_cols, &rows.concat()))
 }
 
-// Invert a matrix using Gaussian elimination
-fn invert_matrix(matrix: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
-    // Handle empty matrix
-    if matrix.is_empty() || matrix[0].is_empty() {
-        eprintln!("Empty matrix in inversion");
-        return None;
-    }
-    
-    let n = matrix.len();
-    
-    // Verify square matrix
-    if matrix.iter().any(|row| row.len() != n) {
-        eprintln!("Cannot invert non-square matrix");
-        return None;
-    }
-    
-    // Create working copies
-    let mut a = matrix.to_vec();
-    let mut inv = identity_matrix(n);
-    
-    // Gaussian elimination with partial pivoting
-    for i in 0..n {
-        // Find pivot with maximum absolute value
-        let mut max_val = 0.0;
-        let mut max_row = i;
-        
-        for k in i..n {
-            let abs_val = a[k][i].abs();
-            if abs_val > max_val {
-                max_val = abs_val;
-                max_row = k;
-            }
-        }
-        
-        // Check if matrix is singular
-        if max_val < 1e-10 {
-            eprintln!("Matrix is nearly singular, inversion may be unstable");
-            return None;
-        }
-        
-        // Swap rows
-        if max_row != i {
-            a.swap(i, max_row);
-            inv.swap(i, max_row);
-        }
-        
-        // Scale the pivot row
-        let diag = a[i][i];
-        for j in 0..n {
-            a[i][j] /= diag;
-            inv[i][j] /= diag;
-        }
-        
-        // Eliminate other rows
-        for k in 0..n {
-            if k != i {
-                let factor = a[k][i];
-                for j in 0..n {
-                    a[k][j] -= factor * a[i][j];
-                    inv[k][j] -= factor * inv[i][j];
-                }
-            }
-        }
-    }
-    
-    // Verify the result by checking that A * A^-1 ≈ I
-    let a_identity = mat_mult(matrix, &inv);
-    if let Some(a_id) = a_identity {
-        let is_close_to_identity = a_id.iter().enumerate().all(|(i, row)| {
-            row.iter().enumerate().all(|(j, &val)| {
-                if i == j {
-                    (val - 1.0).abs() < 1e-8
-                } else {
-                    val.abs() < 1e-8
-                }
-            })
-        });
-        
-        if !is_close_to_identity {
-            eprintln!("Warning: Matrix inversion may be numerically unstable");
-        }
+// Solve A*x = b. Tries a Cholesky factorization first (cheap and stable for the
+// symmetric-positive-definite matrices we expect: tau*Sigma, Omega, the posterior
+// precision), falling back to LU with partial pivoting for the general case.
+fn solve(a: &DMatrix<f64>, b: &DVector<f64>) -> Result<DVector<f64>, LinAlgError> {
+    if let Some(chol) = a.clone().cholesky() {
+        return Ok(chol.solve(b));
     }
-    
-    Some(inv)
+    a.clone()
+        .lu()
+        .solve(b)
+        .ok_or_else(|| LinAlgError::SingularMatrix("matrix is singular or near-singular".into()))
 }
 
-fn to_column_vector(vec: &[f64]) -> Vec<Vec<f64>> {
-    vec.iter().map(|&x| vec![x]).collect()
+// Invert A. Same Cholesky-then-LU strategy as `solve`, used where we genuinely
+// need the explicit inverse (e.g. Omega^-1 appears twice below).
+fn invert(a: &DMatrix<f64>) -> Result<DMatrix<f64>, LinAlgError> {
+    if let Some(chol) = a.clone().cholesky() {
+        return Ok(chol.inverse());
+    }
+    a.clone()
+        .try_inverse()
+        .ok_or_else(|| LinAlgError::SingularMatrix("matrix is singular or near-singular".into()))
 }
 
 // Black-Litterman Model Implementation
 pub fn black_litterman(
-    sigma: &[Vec<f64>], // Covariance matrix (Σ)
-    market_weights: &[f64], // Market capitalization weights (w_m)
-    tau: f64, // Small scaling factor
-    p: &[Vec<f64>], // Views matrix (P)
-    q: &[f64], // Views vector (Q)
-    omega: &[Vec<f64>] // Uncertainty matrix (Ω)
-) -> Vec<f64> {
-    // Check if inputs are valid and have compatible dimensions
-    if sigma.is_empty() || market_weights.is_empty() || p.is_empty() || q.is_empty() || omega.is_empty() {
-        eprintln!("Empty inputs to black_litterman");
-        return Vec::new();
-    }
-    
-    // Validate dimensions
-    let n = sigma.len();
-    if sigma[0].len() != n {
-        eprintln!("Covariance matrix must be square");
-        return Vec::new();
+    sigma: &[Vec<f64>],        // Covariance matrix (Σ)
+    market_weights: &[f64],    // Market capitalization weights (w_m)
+    tau: f64,                  // Small scaling factor
+    delta: f64,                // Risk-aversion coefficient, default ~2.5
+    p: &[Vec<f64>],            // Views matrix (P)
+    q: &[f64],                 // Views vector (Q)
+    omega: &[Vec<f64>],        // Uncertainty matrix (Ω)
+) -> Result<Vec<f64>, LinAlgError> {
+    let sigma_m = to_dmatrix(sigma)?;
+    let n = sigma_m.nrows();
+    if sigma_m.ncols() != n {
+        return Err(LinAlgError::DimensionMismatch(
+            "covariance matrix must be square".into(),
+        ));
     }
-    
     if market_weights.len() != n {
-        eprintln!("Market weights dimension doesn't match covariance matrix");
-        return Vec::new();
+        return Err(LinAlgError::DimensionMismatch(
+            "market weights dimension doesn't match covariance matrix".into(),
+        ));
     }
-    
-    if p[0].len() != n {
-        eprintln!("Views matrix column dimension doesn't match covariance matrix");
-        return Vec::new();
+
+    let p_m = to_dmatrix(p)?;
+    let k = p_m.nrows();
+    if p_m.ncols() != n {
+        return Err(LinAlgError::DimensionMismatch(
+            "views matrix column dimension doesn't match covariance matrix".into(),
+        ));
     }
-    
-    let k = p.len(); // Number of views
-    if omega.len() != k || omega[0].len() != k || q.len() != k {
-        eprintln!("Views dimensions mismatch");
-        return Vec::new();
+
+    let omega_m = to_dmatrix(omega)?;
+    if omega_m.nrows() != k || omega_m.ncols() != k || q.len() != k {
+        return Err(LinAlgError::DimensionMismatch(
+            "views dimensions mismatch".into(),
+        ));
     }
-    
-    // Create tau*sigma matrix
-    let tau_sigma: Vec<Vec<f64>> = sigma.iter().map(|row| 
-        row.iter().map(|&val| val * tau).collect()
-    ).collect();
-    
-    // Calculate pi (implied excess equilibrium returns)
-    let mut pi = vec![0.0; n];
-    
-    // This is more efficient than matrix multiplication for this specific case
-    for i in 0..n {
-        for j in 0..n {
-            pi[i] += tau_sigma[i][j] * market_weights[j];
-        }
+
+    let w = DVector::from_row_slice(market_weights);
+    let q_v = DVector::from_row_slice(q);
+
+    let tau_sigma = &sigma_m * tau;
+    // Implied excess equilibrium returns from reverse optimization: Π = δΣ·w_m
+    let pi = &sigma_m * delta * &w;
+
+    let omega_inv = invert(&omega_m)?;
+    let pt_omega_inv = p_m.transpose() * &omega_inv;
+
+    // Posterior precision: (τΣ)^-1 + P'Ω^-1P. (τΣ)^-1 is formed once with `invert`
+    // and reused for both the precision sum and (τΣ)^-1·Π; the posterior precision
+    // itself is solved against with `solve` rather than inverted.
+    let tau_sigma_inv = invert(&tau_sigma)?;
+    let posterior_precision = &tau_sigma_inv + &pt_omega_inv * &p_m;
+
+    let rhs = &tau_sigma_inv * &pi + &pt_omega_inv * &q_v;
+    let posterior_mean = solve(&posterior_precision, &rhs)?;
+
+    Ok(posterior_mean.iter().copied().collect())
+}
+
+// Mean-variance optimization for portfolio allocation (unconstrained tangency weights)
+pub fn mvo(cov: &[Vec<f64>], arv: Vec<f64>) -> Result<Vec<f64>, LinAlgError> {
+    let cov_m = to_dmatrix(cov)?;
+    let n = cov_m.nrows();
+    if cov_m.ncols() != n || arv.len() != n {
+        return Err(LinAlgError::DimensionMismatch(
+            "incompatible dimensions in MVO inputs".into(),
+        ));
     }
-    
-    // Calculate inverse of tau*sigma
-    let tau_sigma_inv = match invert_matrix(&tau_sigma) {
-        Some(result) => result,
-        None => {
-            eprintln!("Failed to invert tau*sigma matrix");
-            return Vec::new();
-        }
-    };
-    
-    // Calculate inverse of omega
-    let omega_inv = match invert_matrix(omega) {
-        Some(result) => result,
-        None => {
-            eprintln!("Failed to invert omega matrix");
-            return Vec::new();
-        }
-    };
-    
-    // Compute P' (transpose of P)
-    let p_transposed = match transpose(p) {
-        Some(result) => result,
-        None => {
-            eprintln!("Failed to transpose P matrix");
-            return Vec::new();
-        }
-    };
-    
-    // Calculate P' * Omega^-1
-    let pt_omega_inv = match mat_mult(&p_transposed, &omega_inv) {
-        Some(result) => result,
-        None => {
-            eprintln!("Failed in P' * Omega^-1 calculation");
-            return Vec::new();
-        }
-    };
-    
-    // Calculate P' * Omega^-1 * P
-    let p_pt_omega_inv = match mat_mult(&pt_omega_inv, p) {
-        Some(result) => result,
-        None => {
-            eprintln!("Failed in P' * Omega^-1 * P calculation");
-            return Vec::new();
-        }
-    };
-    
-    // Calculate (tau*Sigma)^-1 + P' * Omega^-1 * P
-    let mut posterior_precision = p_pt_omega_inv;
-    for i in 0..n {
-        for j in 0..n {
-            posterior_precision[i][j] += tau_sigma_inv[i][j];
-        }
+
+    let arv_v = DVector::from_row_slice(&arv);
+    let weights = tangency_weights(&cov_m, &arv_v)?;
+    Ok(weights.iter().copied().collect())
+}
+
+// Unconstrained tangency weights Σ^-1·μ, normalized to sum to 1.0. Shared by
+// `mvo` and the per-view re-optimization `idzorek_omega` performs internally.
+fn tangency_weights(sigma_m: &DMatrix<f64>, mu: &DVector<f64>) -> Result<DVector<f64>, LinAlgError> {
+    let mut weights = solve(sigma_m, mu)?;
+    let sum: f64 = weights.iter().sum();
+    if sum.abs() > 1e-10 {
+        weights /= sum;
     }
-    
-    // Calculate posterior covariance
-    let posterior_cov = match invert_matrix(&posterior_precision) {
-        Some(result) => result,
-        None => {
-            eprintln!("Failed to invert posterior precision matrix");
-            return Vec::new();
-        }
-    };
-    
-    // Calculate P' * Omega^-1 * q
-    let q_col = to_column_vector(q);
-    let second_term_result = match mat_mult(&pt_omega_inv, &q_col) {
-        Some(result) => result,
-        None => {
-            eprintln!("Failed in second term calculation");
-            return Vec::new();
+    Ok(weights)
+}
+
+// Posterior mean for a single view in isolation, given its own ω_k on the diagonal.
+fn posterior_mean_single_view(
+    tau_sigma_inv: &DMatrix<f64>,
+    pi: &DVector<f64>,
+    p_row: &DVector<f64>,
+    q_k: f64,
+    omega_k: f64,
+) -> Result<DVector<f64>, LinAlgError> {
+    let pt_omega_inv = p_row / omega_k; // nx1, since Ω is the 1x1 scalar omega_k
+    let posterior_precision = tau_sigma_inv + &pt_omega_inv * p_row.transpose();
+    let rhs = tau_sigma_inv * pi + &pt_omega_inv * q_k;
+    solve(&posterior_precision, &rhs)
+}
+
+/// Derive Ω from per-view confidence levels in [0, 1] (Idzorek's method) instead
+/// of requiring the caller to hand-craft view variances directly. `confidences[k]`
+/// is how sure the caller is about view `k` (`p[k]`, `q[k]`).
+pub fn idzorek_omega(
+    sigma: &[Vec<f64>],
+    market_weights: &[f64],
+    tau: f64,
+    delta: f64,
+    p: &[Vec<f64>],
+    q: &[f64],
+    confidences: &[f64],
+) -> Result<Vec<Vec<f64>>, LinAlgError> {
+    let sigma_m = to_dmatrix(sigma)?;
+    let n = sigma_m.nrows();
+    if sigma_m.ncols() != n || market_weights.len() != n {
+        return Err(LinAlgError::DimensionMismatch(
+            "covariance matrix / market weights shape mismatch".into(),
+        ));
+    }
+
+    let p_m = to_dmatrix(p)?;
+    let k = p_m.nrows();
+    if p_m.ncols() != n || q.len() != k || confidences.len() != k {
+        return Err(LinAlgError::DimensionMismatch(
+            "views / confidences shape mismatch".into(),
+        ));
+    }
+
+    let w_mkt = DVector::from_row_slice(market_weights);
+    let tau_sigma = &sigma_m * tau;
+    // Implied excess equilibrium returns from reverse optimization: Π = δΣ·w_mkt,
+    // same convention black_litterman uses — tau only scales the prior's
+    // uncertainty (τΣ), not the equilibrium return level itself.
+    let pi = &sigma_m * delta * &w_mkt;
+    let tau_sigma_inv = invert(&tau_sigma)?;
+
+    let mut diag = vec![0.0; k];
+    for view in 0..k {
+        let p_row = p_m.row(view).transpose();
+        let q_k = q[view];
+        let confidence = confidences[view].clamp(0.0, 1.0);
+
+        // The asset this view is most opinionated about; used as the scalar
+        // proxy for "how much this view tilts the portfolio".
+        let pivot = (0..n)
+            .max_by(|&a, &b| p_row[a].abs().partial_cmp(&p_row[b].abs()).unwrap())
+            .unwrap();
+
+        // 100%-confidence posterior: the ω_k → 0 limit, which forces P_k·μ = Q_k
+        // exactly rather than dividing by zero.
+        let denom = (p_row.transpose() * &tau_sigma * &p_row)[(0, 0)];
+        if denom.abs() < 1e-12 {
+            diag[view] = 1e-8;
+            continue;
         }
-    };
-    
-    // Calculate (tau*Sigma)^-1 * pi
-    let mut first_term_result = vec![vec![0.0; 1]; n];
-    for i in 0..n {
-        for j in 0..n {
-            first_term_result[i][0] += tau_sigma_inv[i][j] * pi[j];
+        let tilt_scale = (q_k - (p_row.transpose() * &pi)[(0, 0)]) / denom;
+        let mu_100 = &pi + &tau_sigma * &p_row * tilt_scale;
+        let w_100 = tangency_weights(&sigma_m, &mu_100)?;
+        let target_tilt = confidence * (w_100[pivot] - w_mkt[pivot]);
+
+        // Posterior weight deviation on the pivot asset is monotonically
+        // decreasing in ω_k (less confidence → smaller tilt), so bisect for the
+        // ω_k whose tilt matches the confidence-scaled target.
+        let tilt_at = |omega_k: f64| -> Result<f64, LinAlgError> {
+            let mu = posterior_mean_single_view(&tau_sigma_inv, &pi, &p_row, q_k, omega_k)?;
+            let w = tangency_weights(&sigma_m, &mu)?;
+            Ok(w[pivot] - w_mkt[pivot])
+        };
+
+        let mut lo = 1e-10_f64;
+        let mut hi = 1e6_f64;
+        for _ in 0..60 {
+            let mid = 0.5 * (lo + hi);
+            if tilt_at(mid)?.abs() > target_tilt.abs() {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
         }
+        diag[view] = 0.5 * (lo + hi);
+    }
+
+    let mut omega = vec![vec![0.0; k]; k];
+    for (i, omega_i) in diag.into_iter().enumerate() {
+        omega[i][i] = omega_i;
+    }
+    Ok(omega)
+}
+
+/// Box-constrained quadratic-utility allocation via Frank-Wolfe (conditional
+/// gradient). Maximizes U(w) = w'μ − (γ/2)·w'Σw over {w : Σw_i = 1, l_i ≤ w_i ≤ u_i}.
+/// Pass `bounds` of `(0.0, 1.0)` per asset for a long-only portfolio. Avoids the
+/// large short positions `mvo`'s closed-form tangency weights can produce, without
+/// pulling in a full QP dependency.
+pub fn mvo_frank_wolfe(
+    cov: &[Vec<f64>],
+    mu: &[f64],
+    gamma: f64,
+    bounds: &[(f64, f64)],
+    tol: f64,
+    max_iter: usize,
+) -> Result<Vec<f64>, LinAlgError> {
+    let cov_m = to_dmatrix(cov)?;
+    let n = cov_m.nrows();
+    if cov_m.ncols() != n || mu.len() != n || bounds.len() != n {
+        return Err(LinAlgError::DimensionMismatch(
+            "incompatible dimensions in Frank-Wolfe MVO inputs".into(),
+        ));
     }
-    
-    // Combine terms into one vector
-    let mut combined_terms = vec![0.0; n];
-    for i in 0..n {
-        combined_terms[i] = first_term_result[i][0] + second_term_result[i][0];
+
+    let mu_v = DVector::from_row_slice(mu);
+
+    let mut w = DVector::from_element(n, 1.0 / n as f64);
+    clamp_to_bounds(&mut w, bounds);
+    renormalize_to_budget(&mut w, bounds);
+
+    for t in 0..max_iter {
+        let grad = &mu_v - &cov_m * gamma * &w;
+        let s = bounded_budget_lmo(&grad, bounds);
+
+        let duality_gap = (&s - &w).dot(&grad);
+        if duality_gap.abs() < tol {
+            break;
+        }
+
+        let step = 2.0 / (t as f64 + 2.0);
+        w = &w + step * (&s - &w);
     }
-    
-    // Calculate posterior mean
-    let mut posterior_mean = vec![0.0; n];
-    for i in 0..n {
-        for j in 0..n {
-            posterior_mean[i] += posterior_cov[i][j] * combined_terms[j];
+
+    Ok(w.iter().copied().collect())
+}
+
+// Linear-maximization oracle over the bounded-budget polytope
+// {s : l ≤ s ≤ u, Σs = 1}: the vertex maximizing s'·grad, found by
+// water-filling — start every asset at its lower bound, then hand out the
+// remaining budget to assets in order of decreasing gradient until it's
+// exhausted (the fractional-knapsack solution).
+fn bounded_budget_lmo(grad: &DVector<f64>, bounds: &[(f64, f64)]) -> DVector<f64> {
+    let n = grad.len();
+    let mut s: Vec<f64> = bounds.iter().map(|&(l, _)| l).collect();
+    let mut remaining = 1.0 - bounds.iter().map(|&(l, _)| l).sum::<f64>();
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| grad[b].partial_cmp(&grad[a]).unwrap());
+
+    for i in order {
+        if remaining <= 1e-15 {
+            break;
         }
+        let headroom = bounds[i].1 - bounds[i].0;
+        let fill = headroom.min(remaining);
+        s[i] += fill;
+        remaining -= fill;
     }
-    
-    posterior_mean
+
+    DVector::from_vec(s)
 }
 
-// Mean-variance optimization for portfolio allocation
-pub fn mvo(cov: &[Vec<f64>], arv: Vec<f64>) -> Vec<f64> {
-    // Check if inputs are valid and have compatible dimensions
-    if cov.is_empty() || arv.is_empty() {
-        eprintln!("Empty inputs to MVO");
-        return Vec::new();
+// Clamp each component into its [lower, upper] bound.
+fn clamp_to_bounds(w: &mut DVector<f64>, bounds: &[(f64, f64)]) {
+    for i in 0..w.len() {
+        w[i] = w[i].clamp(bounds[i].0, bounds[i].1);
     }
-    
-    // Validate dimensions
-    let n = cov.len();
-    if cov[0].len() != n || arv.len() != n {
-        eprintln!("Incompatible dimensions in MVO inputs");
-        return Vec::new();
+}
+
+// Rescale a bound-feasible point so its weights sum back to the full budget
+// (1.0), shifting the slack across assets in proportion to their remaining
+// headroom so every component stays within its bound.
+fn renormalize_to_budget(w: &mut DVector<f64>, bounds: &[(f64, f64)]) {
+    let sum: f64 = w.iter().sum();
+    let slack = 1.0 - sum;
+    if slack.abs() < 1e-12 {
+        return;
     }
-    
-    // Calculate inverse of covariance matrix
-    let cov_inv = match invert_matrix(cov) {
-        Some(result) => result,
-        None => {
-            eprintln!("Failed to invert covariance matrix");
-            return Vec::new();
-        }
-    };
-    
-    // Calculate optimal weights (more efficient direct calculation)
-    let mut weights = vec![0.0; n];
-    for i in 0..n {
-        for j in 0..n {
-            weights[i] += cov_inv[i][j] * arv[j];
+    if slack > 0.0 {
+        let headroom: f64 = (0..w.len()).map(|i| bounds[i].1 - w[i]).sum();
+        if headroom > 1e-12 {
+            for i in 0..w.len() {
+                w[i] += slack * (bounds[i].1 - w[i]) / headroom;
+            }
         }
-    }
-    
-    // Normalize weights to sum to 1.0
-    let sum: f64 = weights.iter().sum();
-    if sum.abs() > 1e-10 {
-        for i in 0..n {
-            weights[i] /= sum;
+    } else {
+        let headroom: f64 = (0..w.len()).map(|i| w[i] - bounds[i].0).sum();
+        if headroom > 1e-12 {
+            for i in 0..w.len() {
+                w[i] += slack * (w[i] - bounds[i].0) / headroom;
+            }
         }
     }
-    
-    weights
-}
\ No newline at end of file
+}
@@ -2,10 +2,14 @@ use std::error::Error;
 use pyo3::prelude::*;
 use pyo3::types::PyList;
 use std::env;
+use std::time::Duration;
 
 mod io;
 mod optimizer;
 mod litterman;
+mod backtest;
+mod pricing;
+mod indicators;
 
 fn analyze_sentiment(text: &str) -> PyResult<Vec<f64>> {
     unsafe {
@@ -23,8 +27,68 @@ fn analyze_sentiment(text: &str) -> PyResult<Vec<f64>> {
     })
 }
 
+// Run the model against a precomputed set of inputs loaded from Matrix Market
+// files, bypassing the SEC/news/Yahoo fetch entirely. Expects `<dir>/sigma.mtx`,
+// `<dir>/market_weights.mtx`, `<dir>/p.mtx`, `<dir>/q.mtx` and `<dir>/omega.mtx`.
+fn run_from_matrixmarket(dir: &str) -> Result<(), Box<dyn Error>> {
+    let sigma = io::read_matrix_market(&format!("{}/sigma.mtx", dir))?;
+    let market_weights = io::read_matrix_market_vector(&format!("{}/market_weights.mtx", dir))?;
+    let p_values = io::read_matrix_market(&format!("{}/p.mtx", dir))?;
+    let q_values = io::read_matrix_market_vector(&format!("{}/q.mtx", dir))?;
+    let omega = io::read_matrix_market(&format!("{}/omega.mtx", dir))?;
+
+    let tau = 0.025;
+    let delta = 2.5;
+    let posterior_mean = litterman::black_litterman(
+        &sigma,
+        &market_weights,
+        tau,
+        delta,
+        &p_values,
+        &q_values,
+        &omega,
+    )?;
+
+    println!("Posterior mean: {:?}", posterior_mean);
+
+    let updated_weights = litterman::mvo(&sigma, posterior_mean)?;
+    for (i, weight) in updated_weights.iter().enumerate() {
+        println!("asset {}: {:.2}%", i, weight * 100.0);
+    }
+
+    Ok(())
+}
+
+// Evaluate the walk-forward backtest harness over a directory of historical
+// Matrix Market snapshots (see `backtest::load_snapshots`) and print the
+// resulting out-of-sample performance report.
+fn run_backtest(dir: &str, periods_per_year: f64) -> Result<(), Box<dyn Error>> {
+    let snapshots = backtest::load_snapshots(dir)?;
+    let tau = 0.025;
+    let delta = 2.5;
+    let report = backtest::walk_forward(&snapshots, tau, delta, periods_per_year)?;
+    println!("{:#?}", report);
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().collect();
+    if let Some(pos) = args.iter().position(|a| a == "--from-matrixmarket") {
+        let dir = args.get(pos + 1).ok_or("--from-matrixmarket requires a directory argument")?;
+        return run_from_matrixmarket(dir);
+    }
+    if let Some(pos) = args.iter().position(|a| a == "--backtest") {
+        let dir = args.get(pos + 1).ok_or("--backtest requires a directory argument")?;
+        let periods_per_year = args
+            .iter()
+            .position(|a| a == "--periods-per-year")
+            .and_then(|p| args.get(p + 1))
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(252.0);
+        return run_backtest(dir, periods_per_year);
+    }
+
     let tickers = vec!["TSLA", "AAPL", "MSFT", "GOOGL", "AMZN"];
     let mut company_datas = Vec::with_capacity(tickers.len());
     let mut articles = Vec::with_capacity(tickers.len());
@@ -123,27 +187,60 @@ async fn main() -> Result<(), Box<dyn Error>> {
                                         
     // Generate views
     let p_values = optimizer::get_pviews(values.clone());
-    let q_values = optimizer::get_qviews(values);
+    let q_values = optimizer::get_qviews_vector(values);
 
     // Get market data
     let market_weights = io::get_market_weights(sorted_tickers.clone()).await?;
-    let sigma = io::get_covariance_matrix(sorted_tickers.clone()).await?;
-    let omega = io::get_uncertainty_matrix(sorted_tickers.clone());
 
-    // Run Black-Litterman model
+    // --shrink-cov opts into the Ledoit-Wolf shrunk covariance estimate
+    // instead of the raw sample covariance, for a better-conditioned Sigma
+    // before it reaches black_litterman/mvo. The sample-covariance path goes
+    // through the cached, concurrently-fetching DataProvider rather than
+    // get_covariance_matrix's one-ticker-at-a-time fetch.
+    let sigma = if args.iter().any(|a| a == "--shrink-cov") {
+        io::get_covariance_matrix_shrunk(sorted_tickers.clone()).await?
+    } else {
+        let provider = io::DataProvider::new(Duration::from_secs(300))?;
+        provider.get_covariance_matrix(&sorted_tickers).await?
+    };
+
     let tau = 0.025;
+    let delta = 2.5;
+
+    // --idzorek-omega derives the uncertainty matrix from per-view confidence
+    // levels (Idzorek's method) instead of the flat diagonal default.
+    let omega = if args.iter().any(|a| a == "--idzorek-omega") {
+        let confidences = optimizer::get_view_confidences(&q_values);
+        litterman::idzorek_omega(&sigma, &market_weights, tau, delta, &p_values, &q_values, &confidences)?
+    } else {
+        io::get_uncertainty_matrix(sorted_tickers.clone())
+    };
+
+    // Optionally dump the matrices this run produced, so they can be diffed
+    // or reloaded later with --from-matrixmarket
+    if let Some(pos) = args.iter().position(|a| a == "--to-matrixmarket") {
+        let dir = args.get(pos + 1).ok_or("--to-matrixmarket requires a directory argument")?;
+        io::write_matrix_market(&format!("{}/sigma.mtx", dir), &sigma)?;
+        io::write_matrix_market_vector(&format!("{}/market_weights.mtx", dir), &market_weights)?;
+        io::write_matrix_market(&format!("{}/p.mtx", dir), &p_values)?;
+        io::write_matrix_market_vector(&format!("{}/q.mtx", dir), &q_values)?;
+        io::write_matrix_market(&format!("{}/omega.mtx", dir), &omega)?;
+    }
+
+    // Run Black-Litterman model
     let posterior_mean = litterman::black_litterman(
-        &sigma, 
-        &market_weights, 
-        tau, 
-        &p_values, 
-        &q_values, 
+        &sigma,
+        &market_weights,
+        tau,
+        delta,
+        &p_values,
+        &q_values,
         &omega
-    );
+    )?;
 
     println!("Posterior mean: {:?}", posterior_mean);
 
-    let updated_weights = litterman::mvo(&sigma, posterior_mean);
+    let updated_weights = litterman::mvo(&sigma, posterior_mean)?;
     for i in 0..sorted_tickers.len() {
         println!("{}: {:.2}%", sorted_tickers[i], updated_weights[i] * 100.0);
     }
@@ -104,4 +104,29 @@ pub fn get_qviews(sentiment_returns: Vec<f64>) -> Vec<Vec<f64>> {
         q_values.push(row);
     }
     q_values
+}
+
+// Per-view confidence in [0, 1] for idzorek_omega, derived from how strong the
+// view's sentiment-implied outperformance is: a view claiming a large return
+// gap is one we're more sure about than a view claiming a marginal one.
+pub fn get_view_confidences(q_values: &[f64]) -> Vec<f64> {
+    q_values.iter().map(|q| q.abs().min(1.0)).collect()
+}
+
+// black_litterman wants one Q value per view (one row of P), not the full
+// n×n matrix get_qviews produces — reduce each row to the mean of its
+// non-zero entries. Row i of get_qviews is non-zero from column i + 1 on,
+// while row i of get_pviews has its -1 entries only from column i + 2 on.
+pub fn get_qviews_vector(sentiment_returns: Vec<f64>) -> Vec<f64> {
+    get_qviews(sentiment_returns)
+        .iter()
+        .map(|row| {
+            let nonzero: Vec<f64> = row.iter().copied().filter(|v| *v != 0.0).collect();
+            if nonzero.is_empty() {
+                0.0
+            } else {
+                nonzero.iter().sum::<f64>() / nonzero.len() as f64
+            }
+        })
+        .collect()
 }
\ No newline at end of file
@@ -0,0 +1,117 @@
+use statrs::distribution::{ContinuousCDF, Normal};
+
+#[derive(Debug, Clone, Copy)]
+pub enum OptionType {
+    Call,
+    Put,
+}
+
+fn norm_cdf(x: f64) -> f64 {
+    Normal::new(0.0, 1.0).unwrap().cdf(x)
+}
+
+fn norm_pdf(x: f64) -> f64 {
+    (-x * x / 2.0).exp() / (2.0 * std::f64::consts::PI).sqrt()
+}
+
+/// Black-Scholes price of a European option (S = spot, K = strike, r = risk-free
+/// rate, sigma = annualized volatility, t = years to expiry). Puts are derived
+/// from the call price via put-call parity.
+pub fn black_scholes(s: f64, k: f64, r: f64, sigma: f64, t: f64, option: OptionType) -> f64 {
+    let d1 = ((s / k).ln() + (r + sigma * sigma / 2.0) * t) / (sigma * t.sqrt());
+    let d2 = d1 - sigma * t.sqrt();
+
+    let call = s * norm_cdf(d1) - k * (-r * t).exp() * norm_cdf(d2);
+    match option {
+        OptionType::Call => call,
+        OptionType::Put => call - s + k * (-r * t).exp(),
+    }
+}
+
+/// Cox-Ross-Rubinstein binomial tree price for an American option. Checks
+/// early exercise against the continuation value at every node, which
+/// Black-Scholes can't capture.
+pub fn binomial_american(
+    s: f64,
+    k: f64,
+    r: f64,
+    sigma: f64,
+    t: f64,
+    steps: usize,
+    option: OptionType,
+) -> f64 {
+    let dt = t / steps as f64;
+    let u = (sigma * dt.sqrt()).exp();
+    let d = 1.0 / u;
+    let p = ((r * dt).exp() - d) / (u - d);
+    let discount = (-r * dt).exp();
+
+    let payoff = |price: f64| match option {
+        OptionType::Call => (price - k).max(0.0),
+        OptionType::Put => (k - price).max(0.0),
+    };
+
+    // Terminal payoffs across the `steps`-period tree
+    let mut values: Vec<f64> = (0..=steps)
+        .map(|i| payoff(s * u.powi(i as i32) * d.powi((steps - i) as i32)))
+        .collect();
+
+    // Backward induction; at each node take the max of continuation value and
+    // immediate exercise
+    for step in (0..steps).rev() {
+        for i in 0..=step {
+            let continuation = discount * (p * values[i + 1] + (1.0 - p) * values[i]);
+            let spot = s * u.powi(i as i32) * d.powi((step - i) as i32);
+            values[i] = continuation.max(payoff(spot));
+        }
+    }
+
+    values[0]
+}
+
+/// Solve for the implied volatility that reproduces `market_price` under
+/// Black-Scholes, via Newton's method (vega = S·φ(d1)·√T as the derivative).
+pub fn implied_volatility(
+    market_price: f64,
+    s: f64,
+    k: f64,
+    r: f64,
+    t: f64,
+    option: OptionType,
+    initial_guess: f64,
+    tolerance: f64,
+    max_iter: usize,
+) -> Option<f64> {
+    let mut sigma = initial_guess;
+    for _ in 0..max_iter {
+        let price = black_scholes(s, k, r, sigma, t, option);
+        let diff = price - market_price;
+        if diff.abs() < tolerance {
+            return Some(sigma);
+        }
+
+        let d1 = ((s / k).ln() + (r + sigma * sigma / 2.0) * t) / (sigma * t.sqrt());
+        let vega = s * norm_pdf(d1) * t.sqrt();
+        if vega.abs() < 1e-12 {
+            return None;
+        }
+
+        sigma -= diff / vega;
+        if sigma <= 0.0 {
+            sigma = tolerance;
+        }
+    }
+    None
+}
+
+/// Annualized historical volatility from a series of daily closing prices.
+pub fn historical_volatility(closes: &[f64], trading_days_per_year: f64) -> f64 {
+    if closes.len() < 2 {
+        return 0.0;
+    }
+    let log_returns: Vec<f64> = closes.windows(2).map(|w| (w[1] / w[0]).ln()).collect();
+    let mean = log_returns.iter().sum::<f64>() / log_returns.len() as f64;
+    let variance = log_returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>()
+        / (log_returns.len() - 1) as f64;
+    variance.sqrt() * trading_days_per_year.sqrt()
+}